@@ -0,0 +1,166 @@
+//! Exporting missing words as Anki-importable flashcards.
+//!
+//! Finding a gap in a deck is only half the job, so `--export` turns
+//! each missing `DuolingoWord` into a front/back/tag [`Card`] and
+//! writes a deck the user can import straight back into Anki.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::path::Path;
+
+extern crate rusqlite;
+use self::rusqlite::types::ToSql;
+use self::rusqlite::Connection;
+
+use DuolingoWord;
+
+/// A single flashcard, mirroring the simple front/back/tag note shape
+/// used by flashcard tooling.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Card {
+    pub front: String,
+    pub back: String,
+    pub tag: String,
+}
+
+impl<'a> From<&'a DuolingoWord<'a>> for Card {
+    fn from(word: &'a DuolingoWord<'a>) -> Card {
+        Card {
+            front: word.word.to_string(),
+            back: format!(
+                "{} (last studied: {})",
+                word.word_class.unwrap_or("?"),
+                word.last_studied.unwrap_or("?")
+            ),
+            tag: tag_from_word_class(word.word_class),
+        }
+    }
+}
+
+fn tag_from_word_class(word_class: Option<&str>) -> String {
+    match word_class {
+        Some(class) => class.to_lowercase().replace(' ', "_"),
+        None => "unknown".to_string(),
+    }
+}
+
+/// Escape a field for Anki's tab-separated import format: escape the
+/// `\x1f` field separator and any literal tabs so a card's text can't
+/// be mistaken for a column break.
+fn escape_field(field: &str) -> String {
+    field.replace('\x1f', "\\\x1f").replace('\t', "\\t")
+}
+
+/// Write `cards` as a tab-separated Anki import file to `path`.
+///
+/// # Examples:
+/// ```rust,no_run
+/// # use duolingo_checker::export::{Card, write_tsv};
+/// let cards = vec![Card {
+///     front: "Glas".to_string(),
+///     back: "Noun (last studied: 33 minutes ago)".to_string(),
+///     tag: "noun".to_string(),
+/// }];
+/// write_tsv(&cards, "missing.txt").unwrap();
+/// ```
+pub fn write_tsv<P: AsRef<Path>>(cards: &[Card], path: P) -> io::Result<()> {
+    let mut file = File::create(path)?;
+
+    for card in cards {
+        writeln!(
+            file,
+            "{}\t{}\t{}",
+            escape_field(&card.front),
+            escape_field(&card.back),
+            escape_field(&card.tag)
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Insert `cards` directly into the `notes` and `cards` tables of an
+/// Anki collection opened through `connection`.
+///
+/// This reuses the `mid` of a note that already exists in the
+/// collection, so the new notes have a real notetype instead of a
+/// dangling `0`, and reuses that same note's `(did, ord)` card
+/// template(s) so each new note gets matching `cards` rows instead of
+/// being orphaned (Anki reviews cards, not bare notes). That means the
+/// target collection must already contain at least one note; an empty
+/// collection has no notetype or template to borrow, and this returns
+/// an error rather than writing orphaned rows.
+pub fn write_to_collection(cards: &[Card], connection: &Connection) -> rusqlite::Result<()> {
+    let (mid, sample_nid): (i64, i64) =
+        connection.query_row("SELECT mid, id FROM notes LIMIT 1", &[], |row| {
+            (row.get(0), row.get(1))
+        })?;
+    let templates = card_templates(connection, sample_nid)?;
+
+    for card in cards {
+        let fields = format!("{}\x1f{}", card.front, card.back);
+        let guid = note_guid(&card.front);
+        let tags = format!(" {} ", card.tag);
+        let csum = field_checksum(&card.front);
+
+        connection.execute(
+            "INSERT INTO notes (guid, mid, mod, usn, tags, flds, sfld, csum, flags, data) \
+             VALUES (?1, ?2, 0, -1, ?3, ?4, ?5, ?6, 0, '')",
+            &[
+                &guid as &ToSql,
+                &mid as &ToSql,
+                &tags as &ToSql,
+                &fields as &ToSql,
+                &card.front as &ToSql,
+                &csum as &ToSql,
+            ],
+        )?;
+        let nid = connection.last_insert_rowid();
+
+        for &(did, ord) in &templates {
+            connection.execute(
+                "INSERT INTO cards \
+                 (nid, did, ord, mod, usn, type, queue, due, ivl, factor, reps, lapses, left, odue, odid, flags, data) \
+                 VALUES (?1, ?2, ?3, 0, -1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, '')",
+                &[&nid as &ToSql, &did as &ToSql, &ord as &ToSql],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The `(did, ord)` pairs of every card template an existing note
+/// (`nid`) has cards for, used to give newly-inserted notes of the
+/// same notetype matching cards.
+fn card_templates(connection: &Connection, nid: i64) -> rusqlite::Result<Vec<(i64, i64)>> {
+    let mut stmt = connection.prepare("SELECT did, ord FROM cards WHERE nid = ?1")?;
+    let rows = stmt.query_map(&[&nid], |row| (row.get(0), row.get(1)))?;
+
+    let mut templates = Vec::new();
+    for row in rows {
+        templates.push(row?);
+    }
+
+    Ok(templates)
+}
+
+fn note_guid(front: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    front.hash(&mut hasher);
+
+    format!("{:x}", hasher.finish())
+}
+
+/// An approximation of Anki's field checksum (used for duplicate
+/// detection): Anki truncates a field's hash to 32 bits, so this does
+/// too, just with `DefaultHasher` rather than Anki's own sha1-based
+/// one.
+fn field_checksum(field: &str) -> i64 {
+    let mut hasher = DefaultHasher::new();
+    field.hash(&mut hasher);
+
+    (hasher.finish() as u32) as i64
+}
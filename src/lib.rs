@@ -22,15 +22,24 @@ use std::collections::HashSet;
 extern crate nom;
 use nom::IResult;
 
+pub mod normalize;
+use normalize::Normalizer;
+pub mod stem;
+pub mod segment;
+use segment::Dictionary;
+pub mod export;
+
 /// A struct used to hold data from Duolingo.
 #[derive(Debug, PartialEq, Clone)]
 pub struct DuolingoWord<'a> {
     /// The word itself
     pub word: &'a str,
-    /// The type of word. Like Noun or Adjective.
-    pub word_class: &'a str,
-    /// The last time the word was studied
-    pub last_studied: &'a str,
+    /// The type of word. Like Noun or Adjective. Absent when the
+    /// source line was missing this column.
+    pub word_class: Option<&'a str>,
+    /// The last time the word was studied. Absent when the source
+    /// line was missing this column.
+    pub last_studied: Option<&'a str>,
 }
 
 impl<'a> fmt::Display for DuolingoWord<'a> {
@@ -39,93 +48,142 @@ impl<'a> fmt::Display for DuolingoWord<'a> {
             f,
             "Word: {}\tType: {}\tLast Studied: {}",
             self.word,
-            self.word_class,
-            self.last_studied
+            self.word_class.unwrap_or("?"),
+            self.last_studied.unwrap_or("?")
         )
     }
 }
 
+/// A line of Duolingo export that could not be fully parsed.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseError {
+    /// The 1-indexed line number of the offending line.
+    pub line_number: usize,
+    /// The line's contents, for diagnostics.
+    pub line: String,
+    /// Why the line was rejected.
+    pub reason: String,
+}
+
 /// Extract the list of `DuolingoWord`s from the file contents.
 ///
 /// The content should be in the format as specified in the module
-/// docs.
-///
-/// # Note:
-///
-/// The `Err` should be done in a better way.
+/// docs, but this parser is tolerant of the ways hand-cleaned dumps
+/// tend to drift from it: blank lines are skipped, a missing trailing
+/// tab is fine, and `word_class`/`last_studied` may be absent
+/// entirely. A line that doesn't even have a word is recorded as a
+/// `ParseError` rather than aborting the whole run.
 ///
 /// # Examples:
 ///
 /// ```rust
 /// # use duolingo_checker::{DuolingoWord, get_words};
-/// let content = "Glas\tNoun\t33 minutes ago\t\nMann\tNoun\t3 months ago\t";
-/// let words = get_words(content).unwrap();
+/// let content = "Glas\tNoun\t33 minutes ago\t\n\nMann\n\tNoun\nSchrank\tNoun";
+/// let (words, errors) = get_words(content).unwrap();
 ///
 /// let glas_word = DuolingoWord {
 ///     word: "Glas",
-///     word_class: "Noun",
-///     last_studied: "33 minutes ago",
+///     word_class: Some("Noun"),
+///     last_studied: Some("33 minutes ago"),
 /// };
 /// let mann_word = DuolingoWord {
 ///     word: "Mann",
-///     word_class: "Noun",
-///     last_studied: "3 months ago",
+///     word_class: None,
+///     last_studied: None,
+/// };
+/// let schrank_word = DuolingoWord {
+///     word: "Schrank",
+///     word_class: Some("Noun"),
+///     last_studied: None,
 /// };
 ///
-/// assert_eq!(words.len(), 2);
-/// assert_eq!(words[0], glas_word);
-/// assert_eq!(words[1], mann_word);
+/// assert_eq!(words, vec![glas_word, mann_word, schrank_word]);
+/// assert_eq!(errors.len(), 1);
+/// assert_eq!(errors[0].line_number, 4);
 /// ```
-pub fn get_words(content: &str) -> Result<Vec<DuolingoWord>, String> {
+pub fn get_words(content: &str) -> Result<(Vec<DuolingoWord>, Vec<ParseError>), String> {
+    let mut words = Vec::new();
+    let mut errors = Vec::new();
+
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match parse_line(line) {
+            Ok(word) => words.push(word),
+            Err(reason) => errors.push(ParseError {
+                line_number: i + 1,
+                line: line.to_string(),
+                reason,
+            }),
+        }
+    }
+
+    Ok((words, errors))
+}
+
+/// Parse a single Duolingo export line, tolerating a missing trailing
+/// tab and a missing `word_class`/`last_studied` column.
+fn parse_line(line: &str) -> Result<DuolingoWord, String> {
     named!(
-        section<&str>,
+        section_before_tab<&str>,
         map_res!(take_until_and_consume_s!("\t"), std::str::from_utf8)
     );
 
-    named!(parse<DuolingoWord>, do_parse!(
-        word: section >>
-        word_class: section >>
-        last_studied: section >>
-        (DuolingoWord {
-            word, word_class, last_studied
-        })
-    ));
+    let (word, rest) = match section_before_tab(line.as_bytes()) {
+        IResult::Done(rest, word) => (
+            word,
+            std::str::from_utf8(rest).map_err(|e| e.to_string())?,
+        ),
+        // No tab at all: the whole line is just the word.
+        _ => (line, ""),
+    };
 
-    let mut words = Vec::new();
-    for line in content.lines() {
-        let word = match parse(line.as_bytes()) {
-            IResult::Done(_, word) => word,
-            IResult::Error(e) => return Err(format!("{:?}", e)),
-            IResult::Incomplete(needed) => return Err(format!("{:?}", needed)),
-        };
-
-        words.push(word);
+    if word.is_empty() {
+        return Err("line has no word".to_string());
     }
 
-    Ok(words)
+    let mut columns = rest.splitn(2, '\t');
+    let word_class = columns.next().filter(|s| !s.is_empty());
+    let last_studied = columns
+        .next()
+        .map(|s| s.trim_end_matches('\t'))
+        .filter(|s| !s.is_empty());
+
+    Ok(DuolingoWord {
+        word,
+        word_class,
+        last_studied,
+    })
 }
 
-/// Create a `HashSet` from a list of list of words.
+/// Create a `HashSet` of normalized words from a list of list of words.
+///
+/// Each field is passed through `normalizer` before being inserted, so the
+/// resulting set holds owned, normalized keys rather than borrows of the
+/// input.
 ///
 /// # Examples:
 /// ```rust
-/// # use duolingo_checker::build_word_map;
-/// let words = vec![vec!["a", "b", "c"],
+/// # use duolingo_checker::{build_word_map, normalize::Normalizer};
+/// let words = vec![vec!["a", "B", "c"],
 ///                  vec!["d", "e"],
 ///                  vec!["a"]];
-/// let set = build_word_map(&words);
+/// let normalizer = Normalizer::new(true, false, false, None);
+/// let set = build_word_map(&words, &normalizer);
 ///
 /// assert!(set.contains("a"));
 /// assert!(set.contains("b"));
 /// assert!(set.contains("e"));
 /// assert!(!set.contains("f"));
 /// ```
-pub fn build_word_map<'a>(words: &Vec<Vec<&'a str>>) -> HashSet<&'a str> {
+pub fn build_word_map(words: &Vec<Vec<&str>>, normalizer: &Normalizer) -> HashSet<String> {
     let mut map = HashSet::new();
 
     for fields in words {
         for field in fields {
-            map.insert(*field);
+            map.insert(normalizer.normalize(field));
         }
     }
 
@@ -164,3 +222,35 @@ pub fn get_words_from_fields<'a>(fields: &'a Vec<String>) -> Vec<Vec<&'a str>> {
         .map(|x| to_fields(x))
         .collect::<Vec<Vec<&str>>>()
 }
+
+/// Extract the fields from the list of fields, further splitting each
+/// field into dictionary words with `dictionary`.
+///
+/// Use this instead of `get_words_from_fields` for scripts with no
+/// spaces between words (Chinese, Japanese), where a field is one
+/// whole sentence rather than one word.
+///
+/// # Examples:
+/// ```rust
+/// # use duolingo_checker::{get_words_from_fields_segmented, segment::Dictionary};
+/// let fields = vec![format!("我爱北京")];
+/// let dictionary = Dictionary::from_lines("我\t100\n爱\t80\n北京\t90\n");
+///
+/// assert_eq!(get_words_from_fields_segmented(&fields, &dictionary),
+///            vec![vec!["我", "爱", "北京"]]);
+/// ```
+pub fn get_words_from_fields_segmented<'a>(
+    fields: &'a Vec<String>,
+    dictionary: &Dictionary,
+) -> Vec<Vec<&'a str>> {
+    fields
+        .iter()
+        .map(|x| to_fields(x))
+        .map(|sections| {
+            sections
+                .into_iter()
+                .flat_map(|section| dictionary.segment(section))
+                .collect::<Vec<&str>>()
+        })
+        .collect()
+}
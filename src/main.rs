@@ -10,7 +10,11 @@ use std::io::{self, Read};
 use std::path::Path;
 
 extern crate duolingo_checker;
-use duolingo_checker::{build_word_map, get_words, get_words_from_fields};
+use duolingo_checker::{build_word_map, get_words, get_words_from_fields, get_words_from_fields_segmented};
+use duolingo_checker::normalize::Normalizer;
+use duolingo_checker::stem::Language;
+use duolingo_checker::segment::Dictionary;
+use duolingo_checker::export::{self, Card};
 
 
 fn get_db_path<'a>(arguments: &'a ArgMatches) -> &'a Path {
@@ -41,6 +45,38 @@ fn get_contents<S: Read>(mut source: S) -> String {
     }
 }
 
+fn get_language(arguments: &ArgMatches) -> Option<Language> {
+    match arguments.value_of("language") {
+        Some(name) => match Language::parse(name) {
+            Some(language) => Some(language),
+            None => panic!(format!("Unsupported language: {}", name)),
+        },
+        None => None,
+    }
+}
+
+fn get_normalizer(arguments: &ArgMatches) -> Normalizer {
+    Normalizer::new(
+        arguments.is_present("ignore_case"),
+        arguments.is_present("fold_diacritics"),
+        arguments.is_present("strip_articles"),
+        get_language(arguments),
+    )
+}
+
+fn get_dictionary(arguments: &ArgMatches) -> Option<Dictionary> {
+    if arguments.value_of("segment") != Some("cjk") {
+        return None;
+    }
+
+    let path = arguments
+        .value_of("dictionary")
+        .expect("--dictionary is required when --segment is given");
+    let contents = get_contents(File::open(Path::new(path)).unwrap());
+
+    Some(Dictionary::from_lines(&contents))
+}
+
 fn get_fields_from_anki(anki_db: &Path) -> Vec<String> {
     let connection = match Connection::open(anki_db) {
         Ok(conn) => conn,
@@ -68,27 +104,53 @@ fn main() {
 
     let anki_db = get_db_path(&arguments);
     let duolingo_file = get_duolingo_file(&arguments);
+    let normalizer = get_normalizer(&arguments);
+    let dictionary = get_dictionary(&arguments);
+    let segment_duolingo = dictionary.is_some() && arguments.is_present("segment_duolingo");
 
     let duolingo_contents = match duolingo_file {
         Some(file) => get_contents(file),
         None => get_contents(io::stdin()),
     };
 
-    let duolingo_words = match get_words(&duolingo_contents) {
-        Ok(words) => words,
+    let (duolingo_words, parse_errors) = match get_words(&duolingo_contents) {
+        Ok(result) => result,
         Err(reason) => panic!(reason),
     };
 
     let anki_fields = get_fields_from_anki(anki_db);
-    let anki_words = get_words_from_fields(&anki_fields);
+    let anki_words = match dictionary {
+        Some(ref dictionary) => get_words_from_fields_segmented(&anki_fields, dictionary),
+        None => get_words_from_fields(&anki_fields),
+    };
 
-    let word_map = build_word_map(&anki_words);
-    let mut num = 0;
+    let word_map = build_word_map(&anki_words, &normalizer);
+    let mut missing = Vec::new();
     for word in duolingo_words {
-        if !word_map.contains(word.word) {
+        let segments = match dictionary {
+            Some(ref dictionary) if segment_duolingo => dictionary.segment(word.word),
+            _ => vec![word.word],
+        };
+        let known = segments
+            .iter()
+            .all(|segment| word_map.contains(&normalizer.normalize(segment)));
+
+        if !known {
             println!("{}", word);
-            num += 1;
+            missing.push(word);
         }
     }
-    println!("{}", num);
+    println!("{}", missing.len());
+    println!("{} lines skipped", parse_errors.len());
+
+    let cards: Vec<Card> = missing.iter().map(Card::from).collect();
+
+    if let Some(path) = arguments.value_of("export") {
+        export::write_tsv(&cards, path).expect("Failed to write exported flashcards");
+    }
+
+    if let Some(path) = arguments.value_of("export_sqlite") {
+        let connection = Connection::open(Path::new(path)).expect("Failed to open export database");
+        export::write_to_collection(&cards, &connection).expect("Failed to write notes to export database");
+    }
 }
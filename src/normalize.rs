@@ -0,0 +1,144 @@
+//! Normalization of words before comparison.
+//!
+//! Duolingo and Anki store the same word in different shapes: different
+//! case, stray surrounding whitespace or punctuation, diacritics one
+//! side has folded away, or different inflections of the same lemma.
+//! A `Normalizer` bundles the set of transforms to apply so that two
+//! such variants compare equal.
+
+use stem::{self, Language};
+
+/// Which transforms are applied to a word before it is compared.
+///
+/// Each field is independently switched on, mirroring the
+/// `--ignore-case`, `--fold-diacritics`, `--strip-articles`, and
+/// `--language` CLI flags.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Normalizer {
+    /// Lowercase the word.
+    pub ignore_case: bool,
+    /// Fold diacritics to their ASCII base (ä→a, é→e, ...).
+    pub fold_diacritics: bool,
+    /// Strip a leading article (der/die/das, le/la, el/la, the, ...).
+    pub strip_articles: bool,
+    /// Reduce the word to its stem, using this language's rule table.
+    pub language: Option<Language>,
+}
+
+const ARTICLES: &[&str] = &[
+    "der", "die", "das", "den", "dem", "des",
+    "le", "la", "les",
+    "el", "los", "las",
+    "the",
+];
+
+impl Normalizer {
+    /// Create a `Normalizer` with the given transforms enabled.
+    pub fn new(
+        ignore_case: bool,
+        fold_diacritics: bool,
+        strip_articles: bool,
+        language: Option<Language>,
+    ) -> Normalizer {
+        Normalizer {
+            ignore_case,
+            fold_diacritics,
+            strip_articles,
+            language,
+        }
+    }
+
+    /// Apply the configured transforms to `word`, returning an owned,
+    /// normalized copy.
+    ///
+    /// The stemming step, when a `language` is set, runs last so it sees
+    /// the word after case-folding and diacritic-folding have already
+    /// run.
+    ///
+    /// # Examples:
+    /// ```rust
+    /// # use duolingo_checker::normalize::Normalizer;
+    /// # use duolingo_checker::stem::Language;
+    /// let normalizer = Normalizer::new(true, true, true, None);
+    ///
+    /// assert_eq!(normalizer.normalize(" das Glas "), "glas");
+    /// assert_eq!(normalizer.normalize("Männer"), "manner");
+    /// assert_eq!(normalizer.normalize("Äpfel"), "apfel");
+    ///
+    /// // Article stripping matches case-insensitively, so a capitalized
+    /// // article is stripped even without `--ignore-case`.
+    /// let strip_only = Normalizer::new(false, false, true, None);
+    /// assert_eq!(strip_only.normalize("Der Mann"), "Mann");
+    ///
+    /// let stemming = Normalizer::new(true, true, false, Some(Language::German));
+    /// assert_eq!(stemming.normalize("Männer"), "mann");
+    /// ```
+    pub fn normalize(&self, word: &str) -> String {
+        // Trim surrounding whitespace and punctuation ("Glas.", "«Glas»").
+        let mut result = word.trim_matches(|c: char| !c.is_alphanumeric()).to_string();
+
+        if self.strip_articles {
+            result = strip_article(&result);
+        }
+
+        if self.ignore_case {
+            result = result.to_lowercase();
+        }
+
+        if self.fold_diacritics {
+            result = fold_diacritics(&result);
+        }
+
+        result = result.trim().to_string();
+
+        if let Some(language) = self.language {
+            result = stem::stem(&result, language);
+        }
+
+        result
+    }
+}
+
+fn strip_article(word: &str) -> String {
+    // Match articles case-insensitively so stripping still fires when
+    // `--ignore-case` is off, or the article is capitalized (sentence-
+    // initial, or French "Le"/"La").
+    let lower = word.to_lowercase();
+
+    for article in ARTICLES {
+        if let Some(rest) = lower.strip_prefix(article) {
+            if rest.starts_with(' ') {
+                // `article` is plain ASCII, so its byte length matches
+                // the corresponding prefix of `word` regardless of case.
+                return word[article.len()..].trim_start().to_string();
+            }
+        }
+    }
+
+    word.to_string()
+}
+
+fn fold_diacritics(word: &str) -> String {
+    // Covers both cases so folding still works when `--ignore-case` is
+    // off; German nouns like "Äpfel" are always capitalized.
+    word.replace('ß', "ss")
+        .chars()
+        .map(|c| match c {
+            'ä' | 'á' | 'à' | 'â' => 'a',
+            'Ä' | 'Á' | 'À' | 'Â' => 'A',
+            'ë' | 'é' | 'è' | 'ê' => 'e',
+            'Ë' | 'É' | 'È' | 'Ê' => 'E',
+            'ï' | 'í' | 'ì' | 'î' => 'i',
+            'Ï' | 'Í' | 'Ì' | 'Î' => 'I',
+            'ö' | 'ó' | 'ò' | 'ô' => 'o',
+            'Ö' | 'Ó' | 'Ò' | 'Ô' => 'O',
+            'ü' | 'ú' | 'ù' | 'û' => 'u',
+            'Ü' | 'Ú' | 'Ù' | 'Û' => 'U',
+            'ñ' => 'n',
+            'Ñ' => 'N',
+            'ç' => 'c',
+            'Ç' => 'C',
+            other => other,
+        })
+        .collect()
+}
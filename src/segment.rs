@@ -0,0 +1,121 @@
+//! Dictionary-based segmentation for space-less scripts.
+//!
+//! Chinese and Japanese Anki fields often pack a whole sentence card
+//! into a single field with no spaces between words, so splitting only
+//! on the `\x1f` field separator (as [`to_fields`](crate::to_fields)
+//! does) returns one giant "word" and nothing in it matches. A [`Dictionary`]
+//! segments such text the way jieba does: build a DAG of every
+//! dictionary word starting at each character offset, then run a
+//! dynamic-programming pass from the end of the string to find the
+//! segmentation with the highest total log-probability, falling back
+//! to single characters wherever no dictionary word matches.
+
+use std::collections::HashMap;
+
+/// A prefix dictionary of word frequencies, used to segment text with
+/// no whitespace between words.
+#[derive(Debug, Clone)]
+pub struct Dictionary {
+    frequencies: HashMap<String, u64>,
+    total: u64,
+}
+
+impl Dictionary {
+    /// Build a `Dictionary` from `(word, frequency)` pairs.
+    pub fn new(words: Vec<(&str, u64)>) -> Dictionary {
+        let mut frequencies = HashMap::new();
+        let mut total = 0;
+
+        for (word, freq) in words {
+            total += freq;
+            frequencies.insert(word.to_string(), freq);
+        }
+
+        Dictionary { frequencies, total }
+    }
+
+    /// Parse a `Dictionary` from `word\tfrequency` lines, the same
+    /// tab-delimited convention the rest of this crate uses for
+    /// Duolingo's export.
+    ///
+    /// # Examples:
+    /// ```rust
+    /// # use duolingo_checker::segment::Dictionary;
+    /// let dictionary = Dictionary::from_lines("我\t100\n爱\t80\n北京\t90\n");
+    /// assert_eq!(dictionary.segment("我爱北京"), vec!["我", "爱", "北京"]);
+    /// ```
+    pub fn from_lines(content: &str) -> Dictionary {
+        let mut words = Vec::new();
+
+        for line in content.lines() {
+            let mut parts = line.splitn(2, '\t');
+            let word = match parts.next() {
+                Some(word) if !word.is_empty() => word,
+                _ => continue,
+            };
+            let freq: u64 = match parts.next().and_then(|freq| freq.trim().parse().ok()) {
+                Some(freq) => freq,
+                None => continue,
+            };
+
+            words.push((word, freq));
+        }
+
+        Dictionary::new(words)
+    }
+
+    /// Segment `text` into the most probable sequence of dictionary
+    /// words, falling back to single characters at offsets with no
+    /// dictionary match.
+    pub fn segment<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        let boundaries: Vec<usize> = text
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(text.len()))
+            .collect();
+        let n = boundaries.len() - 1;
+
+        if n == 0 {
+            return Vec::new();
+        }
+
+        // route[i] = (best log-probability of segmenting text[i..], the
+        // char offset where the best first word of that segmentation ends)
+        let mut route: Vec<(f64, usize)> = vec![(0.0, n); n + 1];
+
+        for i in (0..n).rev() {
+            let mut best_score = std::f64::NEG_INFINITY;
+            let mut best_end = i + 1;
+
+            for j in (i + 1)..=n {
+                let word = &text[boundaries[i]..boundaries[j]];
+                let freq = match self.frequencies.get(word) {
+                    Some(freq) => *freq,
+                    // Fall back to treating a single character as a
+                    // (rare) word of its own so segmentation always
+                    // makes progress.
+                    None if j == i + 1 => 1,
+                    None => continue,
+                };
+
+                let score = (freq as f64 / (self.total + n as u64) as f64).ln() + route[j].0;
+                if score > best_score {
+                    best_score = score;
+                    best_end = j;
+                }
+            }
+
+            route[i] = (best_score, best_end);
+        }
+
+        let mut segments = Vec::new();
+        let mut i = 0;
+        while i < n {
+            let j = route[i].1;
+            segments.push(&text[boundaries[i]..boundaries[j]]);
+            i = j;
+        }
+
+        segments
+    }
+}
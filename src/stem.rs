@@ -0,0 +1,346 @@
+//! Stemming of inflected words down to a shared key.
+//!
+//! A Duolingo word list tends to collect inflected forms ("Männer",
+//! "gegangen") while an Anki deck stores the lemma ("Mann", "gehen"),
+//! so exact-string matching over-reports words as missing. Stemming
+//! both sides before comparison collapses related forms onto the same
+//! key.
+//!
+//! English uses the classic Porter algorithm (suffix-stripping guided
+//! by a measure of vowel-consonant sequences in the remaining stem).
+//! The other languages use smaller, hand-picked suffix tables that
+//! strip a suffix only when a minimum stem length remains. They catch
+//! common regular inflections only; neither they nor the Porter
+//! stemmer handle irregular forms.
+
+/// A language to select a stemmer's rule table.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Language {
+    German,
+    English,
+    Spanish,
+    French,
+}
+
+impl Language {
+    /// Parse a `--language` flag value (`de`, `en`, `es`, `fr`).
+    pub fn parse(name: &str) -> Option<Language> {
+        match name {
+            "de" => Some(Language::German),
+            "en" => Some(Language::English),
+            "es" => Some(Language::Spanish),
+            "fr" => Some(Language::French),
+            _ => None,
+        }
+    }
+}
+
+/// Reduce `word` to its stem according to `language`'s rule table.
+///
+/// # Examples:
+/// ```rust
+/// # use duolingo_checker::stem::{stem, Language};
+/// assert_eq!(stem("caresses", Language::English), "caress");
+/// assert_eq!(stem("manner", Language::German), "mann");
+/// ```
+pub fn stem(word: &str, language: Language) -> String {
+    match language {
+        Language::English => porter_stem(word),
+        Language::German => strip_suffixes(word, GERMAN_SUFFIXES),
+        Language::Spanish => strip_suffixes(word, SPANISH_SUFFIXES),
+        Language::French => strip_suffixes(word, FRENCH_SUFFIXES),
+    }
+}
+
+// A (suffix, minimum length of the stem left after stripping it) pair,
+// tried in order; the first suffix that both matches and leaves a long
+// enough stem wins.
+type Suffix = (&'static str, usize);
+
+const GERMAN_SUFFIXES: &[Suffix] = &[
+    ("nen", 3),
+    ("ern", 3),
+    ("em", 3),
+    ("er", 3),
+    ("es", 3),
+    ("en", 3),
+    ("e", 3),
+    ("s", 3),
+];
+
+const SPANISH_SUFFIXES: &[Suffix] = &[
+    ("aciones", 4),
+    ("adores", 4),
+    ("ando", 3),
+    ("iendo", 3),
+    ("ar", 3),
+    ("er", 3),
+    ("ir", 3),
+    ("os", 3),
+    ("as", 3),
+    ("a", 3),
+    ("o", 3),
+    ("s", 3),
+];
+
+const FRENCH_SUFFIXES: &[Suffix] = &[
+    ("issons", 4),
+    ("issant", 4),
+    ("ement", 4),
+    ("ons", 3),
+    ("ez", 3),
+    ("er", 3),
+    ("es", 3),
+    ("e", 3),
+    ("s", 3),
+];
+
+fn strip_suffixes(word: &str, suffixes: &[Suffix]) -> String {
+    for &(suffix, min_len) in suffixes {
+        if let Some(stem) = word.strip_suffix(suffix) {
+            if stem.chars().count() >= min_len {
+                return stem.to_string();
+            }
+        }
+    }
+
+    word.to_string()
+}
+
+// The classic Porter stemmer (Porter, 1980): strip suffixes in ordered
+// steps, each rule firing only if the measure `m` of the stem left
+// behind satisfies the rule's condition. `m` counts the number of
+// vowel-consonant sequences in the stem, treating 'y' as a vowel only
+// when it isn't itself preceded by a vowel.
+fn porter_stem(word: &str) -> String {
+    let mut stem: Vec<char> = word.chars().collect();
+
+    step_1a(&mut stem);
+    step_1b(&mut stem);
+    step_1c(&mut stem);
+    step_2(&mut stem);
+    step_3(&mut stem);
+    step_4(&mut stem);
+    step_5a(&mut stem);
+    step_5b(&mut stem);
+
+    stem.into_iter().collect()
+}
+
+fn is_consonant(chars: &[char], i: usize) -> bool {
+    match chars[i] {
+        'a' | 'e' | 'i' | 'o' | 'u' => false,
+        'y' => {
+            if i == 0 {
+                true
+            } else {
+                !is_consonant(chars, i - 1)
+            }
+        }
+        _ => true,
+    }
+}
+
+// The measure `m`: the number of vowel-sequence -> consonant-sequence
+// transitions in `chars`, i.e. how many [C]VC groups the stem has after
+// its optional leading consonant run.
+fn measure(chars: &[char]) -> usize {
+    let mut m = 0;
+    let mut seen_vowel = false;
+    let mut in_consonant_run_after_vowel = false;
+
+    for i in 0..chars.len() {
+        if is_consonant(chars, i) {
+            if seen_vowel && !in_consonant_run_after_vowel {
+                m += 1;
+                in_consonant_run_after_vowel = true;
+            }
+        } else {
+            seen_vowel = true;
+            in_consonant_run_after_vowel = false;
+        }
+    }
+
+    m
+}
+
+fn contains_vowel(chars: &[char]) -> bool {
+    (0..chars.len()).any(|i| !is_consonant(chars, i))
+}
+
+fn ends_double_consonant(chars: &[char]) -> bool {
+    let n = chars.len();
+    n >= 2
+        && chars[n - 1] == chars[n - 2]
+        && is_consonant(chars, n - 1)
+}
+
+// consonant-vowel-consonant, where the final consonant is not w, x, or y.
+fn ends_cvc(chars: &[char]) -> bool {
+    let n = chars.len();
+    n >= 3
+        && is_consonant(chars, n - 3)
+        && !is_consonant(chars, n - 2)
+        && is_consonant(chars, n - 1)
+        && !matches!(chars[n - 1], 'w' | 'x' | 'y')
+}
+
+fn ends_with(chars: &[char], suffix: &str) -> bool {
+    let suffix: Vec<char> = suffix.chars().collect();
+    chars.len() >= suffix.len() && chars[chars.len() - suffix.len()..] == suffix[..]
+}
+
+fn replace_suffix(chars: &mut Vec<char>, suffix: &str, replacement: &str) {
+    let new_len = chars.len() - suffix.chars().count();
+    chars.truncate(new_len);
+    chars.extend(replacement.chars());
+}
+
+fn stem_measure(chars: &[char], suffix: &str) -> usize {
+    measure(&chars[..chars.len() - suffix.chars().count()])
+}
+
+fn step_1a(chars: &mut Vec<char>) {
+    if ends_with(chars, "sses") {
+        replace_suffix(chars, "sses", "ss");
+    } else if ends_with(chars, "ies") {
+        replace_suffix(chars, "ies", "i");
+    } else if ends_with(chars, "ss") {
+        // leave as-is
+    } else if ends_with(chars, "s") {
+        replace_suffix(chars, "s", "");
+    }
+}
+
+fn step_1b(chars: &mut Vec<char>) {
+    let did_eed = if ends_with(chars, "eed") {
+        if stem_measure(chars, "eed") > 0 {
+            replace_suffix(chars, "eed", "ee");
+        }
+        true
+    } else {
+        false
+    };
+
+    if did_eed {
+        return;
+    }
+
+    let stripped = if ends_with(chars, "ed") && contains_vowel(&chars[..chars.len() - 2]) {
+        replace_suffix(chars, "ed", "");
+        true
+    } else if ends_with(chars, "ing") && contains_vowel(&chars[..chars.len() - 3]) {
+        replace_suffix(chars, "ing", "");
+        true
+    } else {
+        false
+    };
+
+    if !stripped {
+        return;
+    }
+
+    if ends_with(chars, "at") || ends_with(chars, "bl") || ends_with(chars, "iz") {
+        chars.push('e');
+    } else if ends_double_consonant(chars) && !matches!(chars[chars.len() - 1], 'l' | 's' | 'z') {
+        chars.pop();
+    } else if measure(chars) == 1 && ends_cvc(chars) {
+        chars.push('e');
+    }
+}
+
+fn step_1c(chars: &mut Vec<char>) {
+    if ends_with(chars, "y") && contains_vowel(&chars[..chars.len() - 1]) {
+        let last = chars.len() - 1;
+        chars[last] = 'i';
+    }
+}
+
+const STEP_2_SUFFIXES: &[(&str, &str)] = &[
+    ("ational", "ate"),
+    ("tional", "tion"),
+    ("enci", "ence"),
+    ("anci", "ance"),
+    ("izer", "ize"),
+    ("abli", "able"),
+    ("alli", "al"),
+    ("entli", "ent"),
+    ("eli", "e"),
+    ("ousli", "ous"),
+    ("ization", "ize"),
+    ("ation", "ate"),
+    ("ator", "ate"),
+    ("alism", "al"),
+    ("iveness", "ive"),
+    ("fulness", "ful"),
+    ("ousness", "ous"),
+    ("aliti", "al"),
+    ("iviti", "ive"),
+    ("biliti", "ble"),
+];
+
+fn step_2(chars: &mut Vec<char>) {
+    for &(suffix, replacement) in STEP_2_SUFFIXES {
+        if ends_with(chars, suffix) && stem_measure(chars, suffix) > 0 {
+            replace_suffix(chars, suffix, replacement);
+            return;
+        }
+    }
+}
+
+const STEP_3_SUFFIXES: &[(&str, &str)] = &[
+    ("icate", "ic"),
+    ("ative", ""),
+    ("alize", "al"),
+    ("iciti", "ic"),
+    ("ical", "ic"),
+    ("ful", ""),
+    ("ness", ""),
+];
+
+fn step_3(chars: &mut Vec<char>) {
+    for &(suffix, replacement) in STEP_3_SUFFIXES {
+        if ends_with(chars, suffix) && stem_measure(chars, suffix) > 0 {
+            replace_suffix(chars, suffix, replacement);
+            return;
+        }
+    }
+}
+
+const STEP_4_SUFFIXES: &[&str] = &[
+    "al", "ance", "ence", "er", "ic", "able", "ible", "ant", "ement", "ment", "ent", "ou", "ism",
+    "ate", "iti", "ous", "ive", "ize",
+];
+
+fn step_4(chars: &mut Vec<char>) {
+    if ends_with(chars, "ion") {
+        let before = &chars[..chars.len() - 3];
+        if measure(before) > 1 && (before.last() == Some(&'s') || before.last() == Some(&'t')) {
+            replace_suffix(chars, "ion", "");
+        }
+        return;
+    }
+
+    for &suffix in STEP_4_SUFFIXES {
+        if ends_with(chars, suffix) && stem_measure(chars, suffix) > 1 {
+            replace_suffix(chars, suffix, "");
+            return;
+        }
+    }
+}
+
+fn step_5a(chars: &mut Vec<char>) {
+    if ends_with(chars, "e") {
+        let before = &chars[..chars.len() - 1];
+        let m = measure(before);
+        if m > 1 || (m == 1 && !ends_cvc(before)) {
+            chars.pop();
+        }
+    }
+}
+
+fn step_5b(chars: &mut Vec<char>) {
+    if measure(chars) > 1 && ends_double_consonant(chars) && chars.last() == Some(&'l') {
+        chars.pop();
+    }
+}